@@ -1,6 +1,9 @@
 //! This module contains the `ChannelBank` struct.
 
-use super::frame_queue::FrameQueue;
+use super::{
+    frame_decoder::{FrameDecoder, DEFAULT_MAX_FRAME_LEN},
+    frame_queue::FrameQueue,
+};
 use crate::{
     params::{ChannelID, MAX_CHANNEL_BANK_SIZE},
     traits::{
@@ -8,12 +11,13 @@ use crate::{
     },
     types::{BlockInfo, Channel, Frame, RollupConfig, StageError, StageResult, SystemConfig},
 };
-use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
+use alloc::{boxed::Box, collections::VecDeque, sync::Arc, vec::Vec};
 use alloy_primitives::Bytes;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use core::fmt::Debug;
 use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
 
 /// [ChannelBank] is a stateful stage that does the following:
 /// 1. Unmarshalls frames from L1 transaction data
@@ -43,6 +47,35 @@ where
     channel_queue: VecDeque<ChannelID>,
     /// The previous stage of the derivation pipeline.
     prev: FrameQueue<DAP, CP, T>,
+    /// Decodes raw L1 transaction data into [Frame]s for [Self::ingest_data].
+    decoder: FrameDecoder,
+}
+
+/// A serializable snapshot of a [ChannelBank]'s in-memory state.
+///
+/// Captures the buffered [Channel]s (in the same FIFO order as `channel_queue`), the L1 origin
+/// they were buffered against, and any bytes still buffered in the internal [FrameDecoder], so
+/// that a fault-proof program can checkpoint the channel bank between L1 blocks and later
+/// [restore](ChannelBank::restore) it, resuming derivation from the exact buffered state without
+/// re-ingesting any prior frames. Encoding channels in FIFO order (rather than relying on the
+/// hash map's iteration order), and capturing the decoder's partial-frame bytes rather than
+/// dropping them, is what makes the snapshot round-trip byte-for-byte across two nodes replaying
+/// the same L1 data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelBankSnapshot {
+    /// The buffered channels, in the same order as `channel_queue`.
+    channels: Vec<(ChannelID, Channel)>,
+    /// The FIFO read order of channel IDs.
+    channel_queue: VecDeque<ChannelID>,
+    /// The L1 origin the snapshot was taken at.
+    origin: Option<BlockInfo>,
+    /// Bytes buffered in the [FrameDecoder] that have not yet formed a complete [Frame].
+    decoder_buffer: Vec<u8>,
+    /// Bytes still to be discarded from an in-progress oversized-frame skip in the
+    /// [FrameDecoder]. See [FrameDecoder]'s field docs for details.
+    decoder_skip_remaining: usize,
+    /// The [FrameDecoder]'s configured `max_frame_length`.
+    decoder_max_frame_length: usize,
 }
 
 impl<DAP, CP, T> ChannelBank<DAP, CP, T>
@@ -53,7 +86,14 @@ where
 {
     /// Create a new [ChannelBank] stage.
     pub fn new(cfg: Arc<RollupConfig>, prev: FrameQueue<DAP, CP, T>, telemetry: T) -> Self {
-        Self { cfg, telemetry, channels: HashMap::new(), channel_queue: VecDeque::new(), prev }
+        Self {
+            cfg,
+            telemetry,
+            channels: HashMap::new(),
+            channel_queue: VecDeque::new(),
+            prev,
+            decoder: FrameDecoder::new(DEFAULT_MAX_FRAME_LEN),
+        }
     }
 
     /// Returns the L1 origin [BlockInfo].
@@ -66,6 +106,40 @@ where
         self.channels.iter().fold(0, |acc, (_, c)| acc + c.size())
     }
 
+    /// Captures the current state of the [ChannelBank] into a [ChannelBankSnapshot].
+    pub fn snapshot(&self) -> ChannelBankSnapshot {
+        let channels = self
+            .channel_queue
+            .iter()
+            .filter_map(|id| self.channels.get(id).map(|channel| (*id, channel.clone())))
+            .collect();
+
+        ChannelBankSnapshot {
+            channels,
+            channel_queue: self.channel_queue.clone(),
+            origin: self.origin().copied(),
+            decoder_buffer: self.decoder.buffered().to_vec(),
+            decoder_skip_remaining: self.decoder.skip_remaining(),
+            decoder_max_frame_length: self.decoder.max_frame_length(),
+        }
+    }
+
+    /// Restores the [ChannelBank]'s buffered channels and decoder state from a previously
+    /// captured [ChannelBankSnapshot], discarding whatever state it currently holds.
+    ///
+    /// Note that the snapshot's `origin` is not applied here, as the L1 origin is owned by the
+    /// previous stage of the pipeline (`prev`). The caller is responsible for ensuring `prev` is
+    /// positioned at the same origin the snapshot was taken at before resuming derivation.
+    pub fn restore(&mut self, snap: ChannelBankSnapshot) {
+        self.channel_queue = snap.channel_queue;
+        self.channels = snap.channels.into_iter().collect();
+        self.decoder = FrameDecoder::from_parts(
+            snap.decoder_max_frame_length,
+            snap.decoder_buffer,
+            snap.decoder_skip_remaining,
+        );
+    }
+
     /// Prunes the Channel bank, until it is below [MAX_CHANNEL_BANK_SIZE].
     /// Prunes from the high-priority channel since it failed to be read.
     pub fn prune(&mut self) -> StageResult<()> {
@@ -114,6 +188,38 @@ where
         self.prune()
     }
 
+    /// Ingests raw L1 transaction data (with the leading version byte already stripped),
+    /// decoding it into zero or more [Frame]s via the internal [FrameDecoder] and applying each
+    /// to the channel bank.
+    ///
+    /// Partial frames are buffered across calls, and frames declaring an oversized
+    /// `frame_data_length` are rejected by the decoder before any allocation is made for their
+    /// data. A rejected frame does not stop the rest of `data` from being decoded: the decoder
+    /// skips past the offending frame internally, so any further, well-formed frames packed into
+    /// the same raw payload are still applied.
+    ///
+    /// This is the entry point for callers sitting in front of the frame-parsing stage(s) of the
+    /// pipeline (i.e. with raw, unparsed L1 transaction bytes in hand); see [Self::next_data]'s
+    /// docs for why the pipeline's own frame source cannot yet be routed through this guard.
+    pub fn ingest_data(&mut self, data: &[u8]) -> StageResult<()> {
+        self.decoder.extend(data);
+        loop {
+            match self.decoder.decode() {
+                Ok(Some(frame)) => self.ingest_frame(frame)?,
+                Ok(None) => return Ok(()),
+                Err(e) => {
+                    self.telemetry.write(
+                        alloy_primitives::Bytes::from(alloc::format!(
+                            "Dropping frame from raw data: {:?}",
+                            e
+                        )),
+                        LogLevel::Warning,
+                    );
+                }
+            }
+        }
+    }
+
     /// Read the raw data of the first channel, if it's timed-out or closed.
     ///
     /// Returns an error if there is nothing new to read.
@@ -160,10 +266,137 @@ where
         }
     }
 
+    /// Drains every ready (timed-out or closed) channel from the `channel_queue` in a single
+    /// pass, returning their data in FIFO order.
+    ///
+    /// Unlike repeated calls to [Self::read], which re-scan the whole `channel_queue` and
+    /// perform an O(n) [VecDeque::remove] for every successful read, this walks the queue once,
+    /// avoiding quadratic behavior when a single L1 block closes many channels at once.
+    /// Post-Canyon it rebuilds the queue from scratch; pre-Canyon it only pops from the front.
+    ///
+    /// This respects the same pre-/post-Canyon split as [Self::read]: pre-Canyon, only the front
+    /// of the queue is ever read, and the scan stops at the first channel that is neither timed
+    /// out nor ready, preserving strict FIFO read order. Post-Canyon, the whole queue is scanned
+    /// in one pass, since [Self::read] itself is allowed to read any ready channel regardless of
+    /// position.
+    pub fn read_all(&mut self) -> StageResult<Vec<Bytes>> {
+        let origin = *self.origin().ok_or(anyhow!("No origin present"))?;
+
+        let mut data = Vec::new();
+
+        if !self.cfg.is_canyon_active(origin.timestamp) {
+            while let Some(&id) = self.channel_queue.front() {
+                let Some(channel) = self.channels.get(&id) else {
+                    self.channel_queue.pop_front();
+                    continue;
+                };
+                let timed_out =
+                    channel.open_block_number() + self.cfg.channel_timeout < origin.number;
+
+                if timed_out {
+                    self.telemetry.write(
+                        alloy_primitives::Bytes::from(alloc::format!("Channel {:?} timed out", id)),
+                        LogLevel::Warning,
+                    );
+                    self.channels.remove(&id);
+                    self.channel_queue.pop_front();
+                    continue;
+                }
+
+                // Pre-Canyon, `read` only ever looks at the front of the queue, so `read_all`
+                // must stop here too instead of skipping ahead to a later, ready channel.
+                if !channel.is_ready() {
+                    break;
+                }
+
+                match channel.frame_data() {
+                    Ok(bytes) => {
+                        self.channels.remove(&id);
+                        self.channel_queue.pop_front();
+                        data.push(bytes);
+                    }
+                    Err(e) => {
+                        // The channel is unrecoverable: remove it so it isn't retried forever,
+                        // matching the successful-read path above, then stop the scan. Pre-Canyon,
+                        // `read` only ever looks at the front of the queue, so a corrupt channel
+                        // there blocks reads the same way a not-ready one would.
+                        self.telemetry.write(
+                            alloy_primitives::Bytes::from(alloc::format!(
+                                "Failed to read channel {:?}: {:?}",
+                                id, e
+                            )),
+                            LogLevel::Warning,
+                        );
+                        self.channels.remove(&id);
+                        self.channel_queue.pop_front();
+                        break;
+                    }
+                }
+            }
+
+            return Ok(data);
+        }
+
+        let mut remaining = VecDeque::with_capacity(self.channel_queue.len());
+
+        for id in self.channel_queue.drain(..) {
+            let Some(channel) = self.channels.get(&id) else { continue };
+            let timed_out = channel.open_block_number() + self.cfg.channel_timeout < origin.number;
+
+            if timed_out {
+                self.telemetry.write(
+                    alloy_primitives::Bytes::from(alloc::format!("Channel {:?} timed out", id)),
+                    LogLevel::Warning,
+                );
+                self.channels.remove(&id);
+                continue;
+            }
+
+            if !channel.is_ready() {
+                remaining.push_back(id);
+                continue;
+            }
+
+            match channel.frame_data() {
+                Ok(bytes) => {
+                    self.channels.remove(&id);
+                    data.push(bytes);
+                }
+                Err(e) => {
+                    // Unlike a not-ready channel, a corrupt one can never become ready later, so
+                    // it is removed entirely here rather than re-parked into `remaining` — parking
+                    // it would retry (and re-fail) the same channel on every future `read_all`
+                    // call forever. Post-Canyon, a single corrupt channel doesn't block channels
+                    // elsewhere in the queue, so the scan continues instead of stopping.
+                    self.telemetry.write(
+                        alloy_primitives::Bytes::from(alloc::format!(
+                            "Failed to read channel {:?}: {:?}",
+                            id, e
+                        )),
+                        LogLevel::Warning,
+                    );
+                    self.channels.remove(&id);
+                }
+            }
+        }
+
+        self.channel_queue = remaining;
+        Ok(data)
+    }
+
     /// Pulls the next piece of data from the channel bank. Note that it attempts to pull data out
     /// of the channel bank prior to loading data in (unlike most other stages). This is to
     /// ensure maintain consistency around channel bank pruning which depends upon the order
     /// of operations.
+    ///
+    /// `prev` (`FrameQueue`) hands back an already-parsed [Frame], so the [FrameDecoder]'s
+    /// `max_frame_length` guard does not sit in front of this path: by the time a [Frame] reaches
+    /// here, `FrameQueue` has already parsed the raw L1 transaction bytes it came from and
+    /// allocated its `data`. Re-encoding and re-decoding the frame through [Self::ingest_data]
+    /// here would not change that; the guard only protects callers that hand raw bytes to
+    /// [Self::ingest_data] directly, upstream of whatever parses L1 transaction data into
+    /// [Frame]s. Guarding this path too requires `FrameQueue` itself to decode through a
+    /// [FrameDecoder] before it ever constructs a [Frame].
     pub async fn next_data(&mut self) -> StageResult<Option<Bytes>> {
         match self.read() {
             Err(StageError::Eof) => {
@@ -175,7 +408,7 @@ where
             data => return data,
         };
 
-        // Load the data into the channel bank
+        // Load the data into the channel bank.
         let frame = self.prev.next_frame().await?;
         self.ingest_frame(frame)?;
         Err(StageError::NotEnoughData)
@@ -212,6 +445,7 @@ where
     async fn reset(&mut self, _: BlockInfo, _: SystemConfig) -> StageResult<()> {
         self.channels.clear();
         self.channel_queue = VecDeque::with_capacity(10);
+        self.decoder = FrameDecoder::new(DEFAULT_MAX_FRAME_LEN);
         Err(StageError::Eof)
     }
 }
@@ -270,6 +504,127 @@ mod tests {
         assert_eq!(channel_bank.size(), current_size);
     }
 
+    #[test]
+    fn test_snapshot_restore_roundtrip() {
+        let traversal = new_populated_test_traversal();
+        let results = vec![Ok(Bytes::from(vec![0x00]))];
+        let dap = TestDAP { results };
+        let retrieval = L1Retrieval::new(traversal, dap, TestTelemetry::new());
+        let frame_queue = FrameQueue::new(retrieval, TestTelemetry::new());
+        let mut channel_bank =
+            ChannelBank::new(Arc::new(RollupConfig::default()), frame_queue, TestTelemetry::new());
+
+        let mut frames = new_test_frames(3);
+        channel_bank.ingest_frame(frames.pop().unwrap()).unwrap();
+        channel_bank.ingest_frame(frames.pop().unwrap()).unwrap();
+
+        // Leave a non-zero `skip_remaining` in the decoder from an oversized frame header, so the
+        // round-trip covers an in-progress skip as well as the buffered channels.
+        let mut oversized_header = vec![0xABu8; 16];
+        oversized_header.extend_from_slice(&1u16.to_be_bytes());
+        oversized_header.extend_from_slice(&(DEFAULT_MAX_FRAME_LEN as u32 + 1).to_be_bytes());
+        channel_bank.ingest_data(&oversized_header).unwrap();
+
+        let snap = channel_bank.snapshot();
+
+        let traversal2 = new_populated_test_traversal();
+        let results2 = vec![Ok(Bytes::from(vec![0x00]))];
+        let dap2 = TestDAP { results: results2 };
+        let retrieval2 = L1Retrieval::new(traversal2, dap2, TestTelemetry::new());
+        let frame_queue2 = FrameQueue::new(retrieval2, TestTelemetry::new());
+        let mut restored = ChannelBank::new(
+            Arc::new(RollupConfig::default()),
+            frame_queue2,
+            TestTelemetry::new(),
+        );
+        restored.restore(snap.clone());
+
+        assert_eq!(restored.snapshot(), snap);
+    }
+
+    #[test]
+    fn test_read_all_pre_canyon_stops_at_first_not_ready() {
+        let traversal = new_populated_test_traversal();
+        let results = vec![Ok(Bytes::from(vec![0x00]))];
+        let dap = TestDAP { results };
+        let retrieval = L1Retrieval::new(traversal, dap, TestTelemetry::new());
+        let frame_queue = FrameQueue::new(retrieval, TestTelemetry::new());
+        let mut channel_bank =
+            ChannelBank::new(Arc::new(RollupConfig::default()), frame_queue, TestTelemetry::new());
+
+        let id_a = [0xAAu8; 16];
+        let id_b = [0xBBu8; 16];
+        channel_bank
+            .ingest_frame(Frame {
+                id: id_a,
+                number: 0,
+                data: Bytes::from_static(b"a"),
+                is_last: true,
+            })
+            .unwrap();
+        channel_bank
+            .ingest_frame(Frame {
+                id: id_b,
+                number: 0,
+                data: Bytes::from_static(b"b"),
+                is_last: false,
+            })
+            .unwrap();
+
+        let data = channel_bank.read_all().unwrap();
+        assert_eq!(data, vec![Bytes::from_static(b"a")]);
+
+        // A later, ready channel must not be read while the not-ready `b` still sits at the
+        // front of the queue: pre-Canyon, `read_all` only ever looks at the front.
+        let id_c = [0xCCu8; 16];
+        channel_bank
+            .ingest_frame(Frame {
+                id: id_c,
+                number: 0,
+                data: Bytes::from_static(b"c"),
+                is_last: true,
+            })
+            .unwrap();
+        let data = channel_bank.read_all().unwrap();
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_read_all_post_canyon_reads_any_ready_channel() {
+        let traversal = new_populated_test_traversal();
+        let results = vec![Ok(Bytes::from(vec![0x00]))];
+        let dap = TestDAP { results };
+        let retrieval = L1Retrieval::new(traversal, dap, TestTelemetry::new());
+        let frame_queue = FrameQueue::new(retrieval, TestTelemetry::new());
+        let cfg = RollupConfig { canyon_time: Some(0), ..Default::default() };
+        let mut channel_bank = ChannelBank::new(Arc::new(cfg), frame_queue, TestTelemetry::new());
+
+        let id_a = [0xAAu8; 16];
+        let id_b = [0xBBu8; 16];
+        // `b` is ingested first but never closes, so it is not ready; `a` is ingested second but
+        // is ready. Post-Canyon, `read_all` must still read `a`.
+        channel_bank
+            .ingest_frame(Frame {
+                id: id_b,
+                number: 0,
+                data: Bytes::from_static(b"b"),
+                is_last: false,
+            })
+            .unwrap();
+        channel_bank
+            .ingest_frame(Frame {
+                id: id_a,
+                number: 0,
+                data: Bytes::from_static(b"a"),
+                is_last: true,
+            })
+            .unwrap();
+
+        let data = channel_bank.read_all().unwrap();
+        assert_eq!(data, vec![Bytes::from_static(b"a")]);
+        assert!(channel_bank.size() > 0);
+    }
+
     #[tokio::test]
     async fn test_read_empty_channel_bank() {
         let traversal = new_populated_test_traversal();
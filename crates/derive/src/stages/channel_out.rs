@@ -0,0 +1,220 @@
+#![cfg(feature = "std")]
+
+//! This module contains the [ChannelOut] struct, the inverse of
+//! [ChannelBank](super::channel_bank::ChannelBank).
+//!
+//! Where [ChannelBank] consumes [Frame]s unmarshalled from L1 transaction data and reassembles
+//! full channel data, [ChannelOut] goes the other way: it accepts raw, uncompressed L2 batch
+//! bytes, streams them through a zlib compressor, and splits the compressed output into
+//! [Frame]s ready to be posted back to L1. This is used by the batcher / proof-construction side
+//! of the world rather than the derivation pipeline itself.
+//!
+//! Gated behind the `std` feature: [ChannelOut::new] draws its
+//! [ChannelID](crate::params::ChannelID) from the thread-local OS RNG (`rand::thread_rng`),
+//! which is unavailable to the `no_std` fault-proof program builds of this crate and is only
+//! ever needed on the batcher side anyway.
+
+use crate::types::Frame;
+use alloc::vec::Vec;
+use alloy_primitives::Bytes;
+use anyhow::{anyhow, Result};
+use miniz_oxide::deflate::core::{compress, CompressorOxide, TDEFLFlush, TDEFLStatus};
+use rand::Rng;
+
+/// The default maximum size of a single [Frame]'s `frame_data`, in bytes.
+///
+/// Mirrors the default value of the `--rollup.max-frame-size` flag used by `op-batcher`.
+pub const MAX_FRAME_SIZE: usize = 120_000;
+
+/// The size, in bytes, of the scratch buffer used to drive the streaming compressor.
+const COMPRESS_BUF_SIZE: usize = 32 * 1024;
+
+/// [ChannelOut] accepts raw L2 batch data, streams it through a zlib compressor, and emits the
+/// compressed output as a sequence of [Frame]s. It is the inverse of
+/// [ChannelBank](super::channel_bank::ChannelBank), which decodes [Frame]s back into channel
+/// data.
+#[derive(Debug)]
+pub struct ChannelOut {
+    /// The randomly generated ID of the channel being built.
+    id: [u8; 16],
+    /// The maximum size of a single [Frame]'s `frame_data`, in bytes.
+    max_frame_size: usize,
+    /// The next frame number to be assigned to an outgoing [Frame].
+    frame_number: u16,
+    /// Whether the channel has been closed. Once closed, no more batches may be added and the
+    /// final [Frame] drained from the channel will have `is_last` set.
+    closed: bool,
+    /// The streaming zlib compressor.
+    compressor: CompressorOxide,
+    /// Compressed bytes produced by the compressor that have not yet been drained into a
+    /// [Frame].
+    ready: Vec<u8>,
+}
+
+impl ChannelOut {
+    /// Creates a new [ChannelOut] with a freshly generated [ChannelID](crate::params::ChannelID),
+    /// capping each emitted [Frame]'s `frame_data` at `max_frame_size` bytes.
+    pub fn new(max_frame_size: usize) -> Self {
+        let mut id = [0u8; 16];
+        rand::thread_rng().fill(&mut id);
+        Self {
+            id,
+            max_frame_size,
+            frame_number: 0,
+            closed: false,
+            compressor: CompressorOxide::default(),
+            ready: Vec::new(),
+        }
+    }
+
+    /// Returns the randomly generated ID of the channel being built.
+    pub fn id(&self) -> [u8; 16] {
+        self.id
+    }
+
+    /// Returns `true` if the channel has been [closed](Self::close).
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Streams `data` through the compressor, buffering the compressed output until it is
+    /// drained by [Self::output_frame].
+    ///
+    /// Returns an error if the channel has already been [closed](Self::close).
+    pub fn add_batch(&mut self, data: Bytes) -> Result<()> {
+        if self.closed {
+            return Err(anyhow!("cannot add a batch to a closed channel"));
+        }
+        self.drive_compressor(&data, TDEFLFlush::Sync)
+    }
+
+    /// Returns the number of compressed bytes ready to be drained into [Frame]s.
+    pub fn ready_bytes(&self) -> usize {
+        self.ready.len()
+    }
+
+    /// Closes the channel, flushing any remaining compressed data.
+    ///
+    /// After closing, [Self::add_batch] will error, and the last [Frame] returned by
+    /// [Self::output_frame] will have `is_last` set to `true`.
+    pub fn close(&mut self) -> Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.drive_compressor(&[], TDEFLFlush::Finish)?;
+        self.closed = true;
+        Ok(())
+    }
+
+    /// Drains up to `max_size` compressed bytes (further capped by `max_frame_size`) into a new
+    /// [Frame].
+    ///
+    /// Returns `None` if there is no data ready to be sent and the channel has not yet been
+    /// [closed](Self::close). Once the channel is closed, the final [Frame] returned here will
+    /// have `is_last` set to `true`, after which subsequent calls return `None`.
+    pub fn output_frame(&mut self, max_size: usize) -> Option<Frame> {
+        if self.ready.is_empty() && (!self.closed || self.frame_number > 0) {
+            return None;
+        }
+
+        let size = max_size.min(self.max_frame_size).min(self.ready.len());
+        let data = self.ready.drain(..size).collect::<Vec<_>>();
+        let is_last = self.closed && self.ready.is_empty();
+
+        let frame = Frame { id: self.id, number: self.frame_number, data: Bytes::from(data), is_last };
+        self.frame_number += 1;
+        Some(frame)
+    }
+
+    /// Drives `input` through the streaming compressor, appending any produced output to
+    /// [Self::ready].
+    fn drive_compressor(&mut self, mut input: &[u8], flush: TDEFLFlush) -> Result<()> {
+        let mut buf = [0u8; COMPRESS_BUF_SIZE];
+        loop {
+            let (status, consumed, produced) = compress(&mut self.compressor, input, &mut buf, flush);
+            self.ready.extend_from_slice(&buf[..produced]);
+            input = &input[consumed..];
+
+            match status {
+                TDEFLStatus::Okay => {
+                    // Every call with a sync flush emits a fresh (empty) flush block, so stop once
+                    // the input is consumed and the output no longer fills the scratch buffer.
+                    if input.is_empty() && produced < buf.len() {
+                        break;
+                    }
+                }
+                TDEFLStatus::Done => break,
+                other => return Err(anyhow!("zlib compression failed: {:?}", other)),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_compress_roundtrip() {
+        let mut out = ChannelOut::new(MAX_FRAME_SIZE);
+        let payload =
+            Bytes::from_static(b"hello world, this is a batch of L2 transaction data to encode");
+        out.add_batch(payload.clone()).unwrap();
+        out.close().unwrap();
+
+        let mut compressed = Vec::new();
+        loop {
+            let frame = out.output_frame(MAX_FRAME_SIZE).unwrap();
+            assert_eq!(frame.id, out.id());
+            compressed.extend_from_slice(&frame.data);
+            if frame.is_last {
+                break;
+            }
+        }
+        assert!(out.output_frame(MAX_FRAME_SIZE).is_none());
+
+        let decompressed = miniz_oxide::inflate::decompress_to_vec_zlib(&compressed).unwrap();
+        assert_eq!(decompressed, payload.to_vec());
+    }
+
+    #[test]
+    fn test_output_frame_respects_max_size() {
+        let mut out = ChannelOut::new(8);
+        out.add_batch(Bytes::from(vec![0u8; 256])).unwrap();
+        out.close().unwrap();
+
+        let mut frame_count = 0;
+        let mut saw_last = false;
+        while let Some(frame) = out.output_frame(usize::MAX) {
+            assert!(frame.data.len() <= 8);
+            frame_count += 1;
+            if frame.is_last {
+                saw_last = true;
+                break;
+            }
+        }
+        assert!(saw_last);
+        assert!(frame_count > 1);
+    }
+
+    #[test]
+    fn test_empty_channel_emits_single_last_frame() {
+        let mut out = ChannelOut::new(MAX_FRAME_SIZE);
+        out.close().unwrap();
+
+        let frame = out.output_frame(MAX_FRAME_SIZE).unwrap();
+        assert!(frame.is_last);
+        // Closing still finishes the zlib stream, so the frame carries an empty compressed payload.
+        assert!(miniz_oxide::inflate::decompress_to_vec_zlib(&frame.data).unwrap().is_empty());
+        assert!(out.output_frame(MAX_FRAME_SIZE).is_none());
+    }
+
+    #[test]
+    fn test_add_batch_after_close_errors() {
+        let mut out = ChannelOut::new(MAX_FRAME_SIZE);
+        out.close().unwrap();
+        assert!(out.add_batch(Bytes::from_static(b"too late")).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! This module contains [FrameDecoder], a streaming decoder for the [Frame]s packed into a
+//! single L1 transaction's data.
+
+use crate::types::{Frame, StageError, StageResult};
+use alloy_primitives::Bytes;
+use anyhow::anyhow;
+use bytes::{Buf, BytesMut};
+
+/// The size, in bytes, of a frame's fixed-size header: `channel_id(16) || frame_number(2) ||
+/// frame_data_length(4)`.
+const FRAME_HEADER_SIZE: usize = 16 + 2 + 4;
+
+/// The default maximum permitted `frame_data_length`, in bytes.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 1_000_000;
+
+/// [FrameDecoder] incrementally parses [Frame]s out of a byte stream, buffering partial frames
+/// until enough data has been appended to complete them.
+///
+/// This borrows the incremental-decoder pattern used by length-delimited codecs (e.g.
+/// `tokio_util`'s `LengthDelimitedCodec`): bytes are appended via [Self::extend], and
+/// [Self::decode] is called in a loop, returning `Ok(None)` rather than erroring when the buffer
+/// does not yet hold a complete frame.
+#[derive(Debug)]
+pub struct FrameDecoder {
+    /// The maximum permitted `frame_data_length`, in bytes.
+    ///
+    /// A hostile `frame_data_length` is rejected *before* any allocation is made for the frame's
+    /// `frame_data`, preventing an OOM while deriving untrusted L1 data.
+    max_frame_length: usize,
+    /// The internal buffer of bytes that have not yet been parsed into a [Frame].
+    buffer: BytesMut,
+    /// The number of bytes still to be discarded from an oversized frame that [Self::decode]
+    /// rejected before its `frame_data` could be buffered.
+    ///
+    /// While this is non-zero, [Self::extend] drops incoming bytes into this count instead of
+    /// buffering them, so the offending frame's payload (and trailing `is_last` byte) flow
+    /// through and out of the decoder without ever being allocated. This is what makes an
+    /// oversized-frame rejection recoverable rather than a permanent stall: once the bad frame's
+    /// declared length has fully drained, decoding resumes at the next frame's header.
+    skip_remaining: usize,
+}
+
+impl FrameDecoder {
+    /// Creates a new [FrameDecoder] that rejects any frame whose declared `frame_data_length`
+    /// exceeds `max_frame_length`.
+    pub fn new(max_frame_length: usize) -> Self {
+        Self { max_frame_length, buffer: BytesMut::new(), skip_remaining: 0 }
+    }
+
+    /// Reconstructs a [FrameDecoder] from previously captured parts: `max_frame_length`, the raw
+    /// bytes still buffered, and the number of bytes left to discard from an in-progress
+    /// oversized-frame skip.
+    pub(super) fn from_parts(max_frame_length: usize, buffer: Vec<u8>, skip_remaining: usize) -> Self {
+        Self { max_frame_length, buffer: BytesMut::from(&buffer[..]), skip_remaining }
+    }
+
+    /// Returns the bytes currently buffered that have not yet formed a complete frame.
+    pub(super) fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns the number of bytes still to be discarded from an in-progress oversized-frame
+    /// skip (see the [Self] field docs).
+    pub(super) fn skip_remaining(&self) -> usize {
+        self.skip_remaining
+    }
+
+    /// Returns the maximum permitted `frame_data_length`, in bytes.
+    pub(super) fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Appends raw bytes (e.g. an L1 transaction's calldata, with the leading version byte
+    /// already stripped) to the internal buffer.
+    ///
+    /// If an oversized frame is being skipped (see [Self::decode]), bytes are first consumed
+    /// into that skip before anything is buffered.
+    pub fn extend(&mut self, data: &[u8]) {
+        let mut data = data;
+        if self.skip_remaining > 0 {
+            let skip = self.skip_remaining.min(data.len());
+            self.skip_remaining -= skip;
+            data = &data[skip..];
+        }
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Attempts to decode a single [Frame] out of the internal buffer.
+    ///
+    /// Returns `Ok(None)` if the buffer does not yet contain a complete frame; the caller should
+    /// [Self::extend] the buffer with more data and try again. Returns an error if the declared
+    /// `frame_data_length` exceeds `max_frame_length`, after discarding the offending frame's
+    /// header and whatever portion of its payload has already been buffered — the remainder is
+    /// skipped as it arrives via [Self::extend], so the rejection only aborts this one `decode`
+    /// call rather than wedging every future call on the same bad header.
+    pub fn decode(&mut self) -> StageResult<Option<Frame>> {
+        if self.buffer.len() < FRAME_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&self.buffer[0..16]);
+        let number = u16::from_be_bytes([self.buffer[16], self.buffer[17]]);
+        let data_length = u32::from_be_bytes([
+            self.buffer[18],
+            self.buffer[19],
+            self.buffer[20],
+            self.buffer[21],
+        ]) as usize;
+
+        if data_length > self.max_frame_length {
+            self.buffer.advance(FRAME_HEADER_SIZE);
+            let to_skip = data_length + 1; // + the trailing `is_last` byte
+            let already_buffered = self.buffer.len().min(to_skip);
+            self.buffer.advance(already_buffered);
+            self.skip_remaining = to_skip - already_buffered;
+
+            return Err(StageError::Custom(anyhow!(
+                "frame data length {} exceeds max_frame_length {}",
+                data_length,
+                self.max_frame_length
+            )));
+        }
+
+        let frame_end = FRAME_HEADER_SIZE + data_length + 1;
+        if self.buffer.len() < frame_end {
+            return Ok(None);
+        }
+
+        self.buffer.advance(FRAME_HEADER_SIZE);
+        let data = self.buffer.split_to(data_length).freeze();
+        let is_last = self.buffer[0] != 0;
+        self.buffer.advance(1);
+
+        Ok(Some(Frame { id, number, data: Bytes::from(data), is_last }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn build_frame_bytes(id: [u8; 16], number: u16, data: &[u8], is_last: bool) -> Vec<u8> {
+        let mut out = vec![];
+        out.extend_from_slice(&id);
+        out.extend_from_slice(&number.to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(data);
+        out.push(is_last as u8);
+        out
+    }
+
+    #[test]
+    fn test_decode_waits_for_more_data() {
+        let mut decoder = FrameDecoder::new(DEFAULT_MAX_FRAME_LEN);
+        let encoded = build_frame_bytes([1u8; 16], 0, &[0xde, 0xad, 0xbe, 0xef], true);
+
+        decoder.extend(&encoded[..FRAME_HEADER_SIZE]);
+        assert_eq!(decoder.decode().unwrap(), None);
+
+        decoder.extend(&encoded[FRAME_HEADER_SIZE..]);
+        let frame = decoder.decode().unwrap().unwrap();
+        assert_eq!(frame.id, [1u8; 16]);
+        assert_eq!(frame.number, 0);
+        assert_eq!(frame.data, Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]));
+        assert!(frame.is_last);
+    }
+
+    #[test]
+    fn test_decode_rejects_oversized_frame() {
+        let mut decoder = FrameDecoder::new(3);
+        let encoded = build_frame_bytes([2u8; 16], 0, &[0xde, 0xad, 0xbe, 0xef], false);
+        decoder.extend(&encoded[..FRAME_HEADER_SIZE]);
+        let err = decoder.decode().unwrap_err();
+        assert_eq!(
+            err,
+            StageError::Custom(anyhow!("frame data length 4 exceeds max_frame_length 3"))
+        );
+    }
+
+    #[test]
+    fn test_decode_recovers_after_oversized_frame() {
+        let mut decoder = FrameDecoder::new(3);
+
+        let bad = build_frame_bytes([2u8; 16], 0, &[0xde, 0xad, 0xbe, 0xef], false);
+        let good = build_frame_bytes([3u8; 16], 1, &[0x01, 0x02], true);
+
+        // Feed the oversized frame and the next, well-formed frame back-to-back, as would
+        // happen if they both arrived within the same L1 transaction's data.
+        decoder.extend(&bad);
+        decoder.extend(&good);
+
+        let err = decoder.decode().unwrap_err();
+        assert_eq!(
+            err,
+            StageError::Custom(anyhow!("frame data length 4 exceeds max_frame_length 3"))
+        );
+
+        // The decoder should recover and decode the next frame rather than re-returning the
+        // same error forever.
+        let frame = decoder.decode().unwrap().unwrap();
+        assert_eq!(frame.id, [3u8; 16]);
+        assert_eq!(frame.data, Bytes::from_static(&[0x01, 0x02]));
+        assert!(frame.is_last);
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let mut decoder = FrameDecoder::new(DEFAULT_MAX_FRAME_LEN);
+        let frame = Frame { id: [9u8; 16], number: 7, data: Bytes::from_static(b"batch"), is_last: true };
+
+        decoder.extend(&build_frame_bytes(frame.id, frame.number, &frame.data, frame.is_last));
+        let decoded = decoder.decode().unwrap().unwrap();
+        assert_eq!(decoded, frame);
+    }
+
+    #[test]
+    fn test_max_frame_length_round_trips_through_from_parts() {
+        let decoder = FrameDecoder::new(42);
+        assert_eq!(decoder.max_frame_length(), 42);
+
+        let rebuilt = FrameDecoder::from_parts(decoder.max_frame_length(), vec![], 0);
+        assert_eq!(rebuilt.max_frame_length(), 42);
+    }
+}